@@ -2,73 +2,209 @@ use derive_more::Display;
 use derive_more::From;
 use log::{error, info, warn};
 use std::future::poll_fn;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::task::Poll;
-use tokio::io::{self, AsyncBufReadExt, AsyncRead, BufReader, Lines};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::fs::{create_dir_all, File};
+use tokio::io::{self, AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader, Lines};
 use tokio::process::Command;
 use tokio::select;
 
+/// Number of trailing lines of a failed command's log file to surface
+/// in the error message so the user doesn't have to go digging for it
+const LOG_EXCERPT_LINES: usize = 25;
+
 #[derive(Debug, From, Display)]
 pub enum CommandError {
     #[display(fmt = "IO Error occurred while executing command: {}", _0)]
     IO(io::Error),
-    #[display(fmt = "Provided command string didn't contain a command. (Was it empty?)")]
-    MissingCommand,
-    #[display(fmt = "Process exited with non-zero exit code: Code {}", _0)]
-    NoZeroExitCode(i32),
+    #[display(
+        fmt = "Command `{}` exited with non-zero exit code: Code {}\n{}",
+        command,
+        code,
+        output
+    )]
+    NoZeroExitCode {
+        code: i32,
+        command: String,
+        output: String,
+    },
+    #[display(
+        fmt = "Command `{}` was terminated by a signal before it could exit\n{}",
+        command,
+        output
+    )]
+    Terminated { command: String, output: String },
 }
 
 type CommandResult<T> = Result<T, CommandError>;
 
-/// Executes the provided command with the arguments provided
-pub async fn run_command(
+/// Captured output of a command that completed with a zero exit code
+#[derive(Debug)]
+pub struct CommandOutput {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Executes the provided command with the arguments provided capturing
+/// the stdout and stderr text in addition to streaming it to the logger.
+/// Useful for commands whose output needs to be parsed (e.g `git rev-parse`)
+/// rather than just observed
+pub async fn run_command_output(
     working_dir: impl AsRef<Path>,
     command: &str,
     args: &[&str],
-) -> CommandResult<()> {
-    let mut command = Command::new(command);
-    command.args(args);
-    command.current_dir(working_dir);
-    command.stderr(Stdio::piped());
-    command.stdout(Stdio::piped());
-    apply_env(&mut command);
-
-    let exit_status = pipe_and_wait(command).await?;
-    let code = exit_status.code().unwrap_or(0);
+) -> CommandResult<CommandOutput> {
+    let mut cmd = Command::new(command);
+    cmd.args(args);
+    cmd.current_dir(working_dir);
+    cmd.stderr(Stdio::piped());
+    cmd.stdout(Stdio::piped());
+    apply_env(&mut cmd);
+
+    let mut stdout_lines = Vec::new();
+    let mut stderr_lines = Vec::new();
+    let status = pipe_and_wait(
+        cmd,
+        Some(&mut stdout_lines),
+        Some(&mut stderr_lines),
+        None,
+    )
+    .await?;
+    let code = match status.code() {
+        Some(code) => code,
+        None => {
+            return Err(CommandError::Terminated {
+                command: format_command(command, args),
+                output: stderr_lines.join("\n"),
+            })
+        }
+    };
     if code != 0 {
-        return Err(CommandError::NoZeroExitCode(code));
+        return Err(CommandError::NoZeroExitCode {
+            code,
+            command: format_command(command, args),
+            output: stderr_lines.join("\n"),
+        });
     }
 
-    Ok(())
+    Ok(CommandOutput {
+        status,
+        stdout: stdout_lines.join("\n"),
+        stderr: stderr_lines.join("\n"),
+    })
 }
 
-/// Executes the provided command in the provided working directory
-/// in this case the command is a format string which can contain
-/// format arguments (i.e. {0} {1}) these variables are provided in
-/// the `args_in` slice
-pub async fn run_command_format(
-    working_dir: impl AsRef<Path>,
-    command: &str,
-    args_in: &[&str],
-) -> CommandResult<()> {
-    let (cmd, args) = split_command(command).ok_or(CommandError::MissingCommand)?;
-    let args = transform_args(args, args_in);
-
-    let mut command = Command::new(cmd);
-    command.args(&args);
-    command.current_dir(working_dir);
-    command.stderr(Stdio::piped());
-    command.stdout(Stdio::piped());
-    apply_env(&mut command);
-
-    let exit_status = pipe_and_wait(command).await?;
-    let code = exit_status.code().unwrap_or(0);
-    if code != 0 {
-        return Err(CommandError::NoZeroExitCode(code));
+/// Formats a command and its arguments back into a single string for
+/// inclusion in error messages
+fn format_command(command: &str, args: &[&str]) -> String {
+    if args.is_empty() {
+        command.to_string()
+    } else {
+        format!("{command} {}", args.join(" "))
     }
+}
 
-    Ok(())
+/// Tees a command's invocation to a timestamped log file under a `logs/`
+/// directory so a failed build leaves a record beyond the terminal
+/// scrollback. Every stdout/stderr line is also written to disk, and a
+/// failing command's error carries the tail of that file
+pub struct LoggedCommand {
+    logs_dir: PathBuf,
+}
+
+impl LoggedCommand {
+    /// Creates a logger that writes log files under `<root>/logs`
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            logs_dir: root.as_ref().join("logs"),
+        }
+    }
+
+    /// Executes the provided command teeing its output to a log file,
+    /// including the tail of that file in the error on non-zero exit.
+    /// Returns the real exit code of the process on success
+    pub async fn run(
+        &self,
+        working_dir: impl AsRef<Path>,
+        command: &str,
+        args: &[&str],
+    ) -> CommandResult<i32> {
+        let mut cmd = Command::new(command);
+        cmd.args(args);
+        cmd.current_dir(working_dir);
+        cmd.stderr(Stdio::piped());
+        cmd.stdout(Stdio::piped());
+        apply_env(&mut cmd);
+
+        let (log_path, mut log_file) = self.create_log_file(command).await?;
+        let exit_status = pipe_and_wait(cmd, None, None, Some(&mut log_file)).await?;
+        // Make sure every teed line is on disk before the tail is read back
+        log_file.flush().await?;
+        let code = match exit_status.code() {
+            Some(code) => code,
+            None => {
+                return Err(CommandError::Terminated {
+                    command: format_command(command, args),
+                    output: read_log_tail(&log_path).await,
+                })
+            }
+        };
+        if code != 0 {
+            return Err(CommandError::NoZeroExitCode {
+                code,
+                command: format_command(command, args),
+                output: read_log_tail(&log_path).await,
+            });
+        }
+
+        Ok(code)
+    }
+
+    /// Creates a new timestamped log file for `command` inside the logs
+    /// directory, creating the directory itself if it doesn't exist yet
+    async fn create_log_file(&self, command: &str) -> CommandResult<(PathBuf, File)> {
+        create_dir_all(&self.logs_dir).await?;
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis())
+            .unwrap_or_default();
+        // Concurrent invocations can share a millisecond, so a per-process
+        // counter keeps each one's file name distinct
+        static NEXT_LOG_ID: AtomicUsize = AtomicUsize::new(0);
+        let id = NEXT_LOG_ID.fetch_add(1, Ordering::Relaxed);
+        let label = sanitize_log_label(command);
+        let log_path = self.logs_dir.join(format!("{label}-{timestamp}-{id}.log"));
+
+        let file = File::create(&log_path).await?;
+        Ok((log_path, file))
+    }
+}
+
+/// Replaces characters that don't belong in a file name so the command
+/// can be embedded directly into the log file name
+fn sanitize_log_label(command: &str) -> String {
+    command
+        .chars()
+        .map(|char| if char.is_alphanumeric() { char } else { '_' })
+        .collect()
+}
+
+/// Reads back the last [`LOG_EXCERPT_LINES`] lines of the log file at
+/// `path`, used to give a failing command's error a captured excerpt
+async fn read_log_tail(path: &Path) -> String {
+    let contents = match tokio::fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(_) => return String::new(),
+    };
+
+    let lines: Vec<&str> = contents.lines().collect();
+    let start = lines.len().saturating_sub(LOG_EXCERPT_LINES);
+    lines[start..].join("\n")
 }
 
 /// Applies the build tools specific command environment variables
@@ -123,8 +259,17 @@ where
 
 /// Spawns the command child piping its output to the error logging for
 /// the application and waiting until the process exists returning the
-/// exit status of the program or an Error
-async fn pipe_and_wait(mut command: Command) -> CommandResult<ExitStatus> {
+/// exit status of the program or an Error. When `stdout_sink`/`stderr_sink`
+/// are provided each line read from the respective stream is also pushed
+/// into them so callers can inspect the full captured output afterwards,
+/// and when `log_file` is provided every line from either stream is also
+/// teed to it
+async fn pipe_and_wait(
+    mut command: Command,
+    mut stdout_sink: Option<&mut Vec<String>>,
+    mut stderr_sink: Option<&mut Vec<String>>,
+    mut log_file: Option<&mut File>,
+) -> CommandResult<ExitStatus> {
     let mut child = command.spawn()?;
 
     let mut stdout = OptionalReader::new(child.stdout.take());
@@ -175,12 +320,24 @@ async fn pipe_and_wait(mut command: Command) -> CommandResult<ExitStatus> {
                 let result = result?;
                 if let Some(line) = result {
                     pipe_line(&line, &mut errored);
+                    if let Some(log_file) = log_file.as_deref_mut() {
+                        write_log_line(log_file, &line).await;
+                    }
+                    if let Some(sink) = stdout_sink.as_deref_mut() {
+                        sink.push(line);
+                    }
                 }
             }
             result = stderr.next_line() => {
                 let result = result?;
                 if let Some(line) = result {
                     pipe_line(&line, &mut errored);
+                    if let Some(log_file) = log_file.as_deref_mut() {
+                        write_log_line(log_file, &line).await;
+                    }
+                    if let Some(sink) = stderr_sink.as_deref_mut() {
+                        sink.push(line);
+                    }
                 }
             }
             result = child.wait() => {
@@ -191,47 +348,16 @@ async fn pipe_and_wait(mut command: Command) -> CommandResult<ExitStatus> {
     }
 }
 
-/// Splits the command into the command itself and a vector
-/// containing the additional arguments
-fn split_command(value: &str) -> Option<(&str, Vec<&str>)> {
-    let mut parts = value.split_whitespace();
-    let command = parts.next()?;
-    let args = parts.collect::<Vec<&str>>();
-    Some((command, args))
-}
-
-/// Transforms the provided `args` formatting them replacing their
-/// values with those stored in the `args_in` slice
-fn transform_args<'a: 'b, 'b>(args: Vec<&'a str>, args_in: &'a [&str]) -> Vec<&'b str> {
-    /// Parses a format value from the provided `value`
-    /// returning the index stored inside it or None if
-    /// it could not be parsed as a format
-    fn parse_format(value: &str) -> Option<usize> {
-        let start = value.find('{')?;
-        let end = value.find('}')?;
-        if end <= start {
-            return None;
-        }
-        let format = &value[start + 1..end];
-        format.parse::<usize>().ok()
-    }
-
-    let mut out = Vec::with_capacity(args.len());
-    for arg in args {
-        if let Some(index) = parse_format(arg) {
-            if let Some(value) = args_in.get(index) {
-                out.push(*value);
-                continue;
-            }
-        }
-        out.push(arg)
-    }
-    out
+/// Appends a line to a log file, ignoring I/O errors since a logging
+/// hiccup shouldn't be allowed to fail the command it's observing
+async fn write_log_line(log_file: &mut File, line: &str) {
+    let _ = log_file.write_all(line.as_bytes()).await;
+    let _ = log_file.write_all(b"\n").await;
 }
 
 #[cfg(test)]
 mod test {
-    use crate::cmd::{run_command_format, CommandError, CommandResult};
+    use crate::cmd::{run_command_output, CommandError, CommandResult, LoggedCommand};
     use env_logger::WriteStyle;
     use log::LevelFilter;
     use std::env::current_dir;
@@ -245,33 +371,32 @@ mod test {
     }
 
     #[tokio::test]
-    async fn test() -> CommandResult<()> {
+    async fn test_output() -> CommandResult<()> {
         init_logger();
 
         let working_dir = current_dir()?;
 
-        let command = "bash ./test/test.sh {0}";
-        let args = ["target"];
+        let output = run_command_output(&working_dir, "git", &["rev-parse", "HEAD"]).await?;
+        assert!(!output.stdout.trim().is_empty());
 
-        run_command_format(&working_dir, command, &args).await
+        Ok(())
     }
 
     #[tokio::test]
-    async fn test_err() -> CommandResult<()> {
+    async fn test_logged_command_err() -> CommandResult<()> {
         init_logger();
 
         let working_dir = current_dir()?;
-
-        let command = "bash ./test/test_err.sh {0}";
-        let args = ["target"];
+        let logged = LoggedCommand::new(&working_dir);
         let error_code = 5;
 
-        let err = run_command_format(&working_dir, command, &args)
+        let err = logged
+            .run(&working_dir, "bash", &["./test/test_err.sh", "target"])
             .await
             .unwrap_err();
 
         match err {
-            CommandError::NoZeroExitCode(code) => {
+            CommandError::NoZeroExitCode { code, .. } => {
                 assert_eq!(code, error_code)
             }
             err => return Err(err),
@@ -0,0 +1,96 @@
+use crate::cmd::{CommandError, LoggedCommand};
+use async_trait::async_trait;
+use derive_more::{Display, From};
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, From, Display)]
+pub enum BackendError {
+    #[display(fmt = "IO Error occurred while working with repositories: {}", _0)]
+    IO(io::Error),
+    #[display(fmt = "Unable to execute git command: {}", _0)]
+    Command(CommandError),
+    #[display(fmt = "Git error: {}", _0)]
+    Git(git2::Error),
+    #[display(fmt = "Background git task panicked: {}", _0)]
+    TaskJoin(tokio::task::JoinError),
+}
+
+pub type BackendResult<T> = Result<T, BackendError>;
+
+/// Abstracts the git operations the build pipeline needs over an
+/// interchangeable backend, letting callers opt into the system `git`
+/// binary, an in-process implementation, or a third-party backend
+/// without changing how repositories are cloned and updated
+#[async_trait]
+pub trait Backend: Send + Sync {
+    /// Clones `url` into `working_dir/name`
+    async fn clone(&self, working_dir: &Path, url: &str, name: &str) -> BackendResult<()>;
+
+    /// Checks out the given ref (branch, tag or commit) in the repository at `path`
+    async fn checkout(&self, path: &Path, reference: &str) -> BackendResult<()>;
+
+    /// Returns the commit hash currently checked out in the repository at `path`
+    async fn current_commit(&self, path: &Path) -> BackendResult<String>;
+
+    /// Initializes and updates (recursively) the submodules of the
+    /// repository at `path`
+    async fn update_submodules(&self, path: &Path) -> BackendResult<()>;
+
+    /// Checks whether `path` contains a valid repository for this backend
+    fn is_valid(&self, path: &Path) -> bool;
+}
+
+/// Backend that shells out to the system `git` binary, logging clone,
+/// checkout and submodule invocations via [`LoggedCommand`]; the
+/// `rev-parse` used by `current_commit` is captured but not logged to a file
+pub struct CliBackend {
+    logger: LoggedCommand,
+}
+
+impl CliBackend {
+    /// Creates a CLI backend that writes its logged invocations under `<root>/logs`
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            logger: LoggedCommand::new(root),
+        }
+    }
+}
+
+#[async_trait]
+impl Backend for CliBackend {
+    async fn clone(&self, working_dir: &Path, url: &str, name: &str) -> BackendResult<()> {
+        self.logger
+            .run(working_dir, "git", &["clone", url, name])
+            .await?;
+        Ok(())
+    }
+
+    async fn checkout(&self, path: &Path, reference: &str) -> BackendResult<()> {
+        self.logger
+            .run(path, "git", &["checkout", reference])
+            .await?;
+        Ok(())
+    }
+
+    async fn current_commit(&self, path: &Path) -> BackendResult<String> {
+        let output = crate::cmd::run_command_output(path, "git", &["rev-parse", "HEAD"]).await?;
+        Ok(output.stdout.trim().to_string())
+    }
+
+    async fn update_submodules(&self, path: &Path) -> BackendResult<()> {
+        self.logger
+            .run(
+                path,
+                "git",
+                &["submodule", "update", "--init", "--recursive"],
+            )
+            .await?;
+        Ok(())
+    }
+
+    fn is_valid(&self, path: &Path) -> bool {
+        let path = path.join(".git");
+        path.exists() && path.is_dir()
+    }
+}
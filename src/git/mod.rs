@@ -0,0 +1,159 @@
+pub(crate) mod backend;
+pub(crate) mod native;
+
+use crate::fs::{create_directory, remove_existing};
+use crate::git::backend::{Backend, BackendError};
+use crate::RootContext;
+use derive_more::{Display, From};
+use log::info;
+use std::io;
+use std::path::{Path, PathBuf};
+use tokio::fs::create_dir_all;
+use tokio::try_join;
+
+#[derive(Debug, From, Display)]
+pub enum RepositoryError {
+    #[display(fmt = "IO Error occurred while working with repositories: {}", _0)]
+    IO(io::Error),
+    #[display(fmt = "Git backend operation failed: {}", _0)]
+    Backend(BackendError),
+}
+
+type RepoResult<T> = Result<T, RepositoryError>;
+
+const BUILD_DATA_URL: &str = "https://hub.spigotmc.org/stash/scm/spigot/builddata.git";
+const BUKKIT_URL: &str = "https://hub.spigotmc.org/stash/scm/spigot/bukkit.git";
+const CRAFT_BUKKIT_URL: &str = "https://hub.spigotmc.org/stash/scm/spigot/craftbukkit.git";
+const SPIGOT_URL: &str = "https://hub.spigotmc.org/stash/scm/spigot/spigot.git";
+
+pub(crate) async fn init_repositories(
+    root: &RootContext<'_>,
+    backend: &dyn Backend,
+) -> RepoResult<Repositories> {
+    let (build_data, bukkit, craft_bukkit, spigot) = try_join!(
+        init_repository(root, backend, BUILD_DATA_URL, "BuildData"),
+        init_repository(root, backend, BUKKIT_URL, "Bukkit"),
+        init_repository(root, backend, CRAFT_BUKKIT_URL, "CraftBukkit"),
+        init_repository(root, backend, SPIGOT_URL, "Spigot"),
+    )?;
+
+    info!("{build_data:?}");
+    info!("{bukkit:?}");
+    info!("{craft_bukkit:?}");
+    info!("{spigot:?}");
+
+    Ok(Repositories {
+        build_data,
+        bukkit,
+        craft_bukkit,
+        spigot,
+    })
+}
+
+async fn init_repository(
+    root: &RootContext<'_>,
+    backend: &dyn Backend,
+    url: &'static str,
+    name: &'static str,
+) -> RepoResult<Repository> {
+    let path = root.root_path.join(name);
+    info!("{path:?}");
+    create_directory(&path).await?;
+    // If the git is not valid we must remove it and clone again
+    if !backend.is_valid(&path) {
+        remove_existing(&path).await?;
+        create_dir_all(&path).await?;
+        backend.clone(root.root_path, url, name).await?;
+    }
+
+    let repository = Repository { url, name, path };
+    // Always run submodule init/update: a fresh clone has none checked
+    // out yet, and an already-valid repo may have gained submodules
+    // since the last time it was cloned
+    repository.update_submodules(backend).await?;
+
+    Ok(repository)
+}
+
+#[derive(Debug)]
+pub struct Repository {
+    url: &'static str,
+    name: &'static str,
+    path: PathBuf,
+}
+
+impl Repository {
+    /// Creates a repository handle for an already-cloned checkout at `path`
+    pub(crate) fn new(url: &'static str, name: &'static str, path: PathBuf) -> Self {
+        Self { url, name, path }
+    }
+
+    /// Initializes and updates this repository's submodules, recursively.
+    /// Safe to call again after a [`Backend::checkout`] to a different ref
+    /// to pick up whatever submodules that ref expects
+    pub async fn update_submodules(&self, backend: &dyn Backend) -> RepoResult<()> {
+        backend.update_submodules(&self.path).await?;
+        Ok(())
+    }
+
+    /// The repository's display name (e.g. `"BuildData"`)
+    pub(crate) fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The path the repository was cloned to
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// The four repositories required for a Spigot build, cloned by
+/// [`init_repositories`]
+pub(crate) struct Repositories {
+    pub build_data: Repository,
+    pub bukkit: Repository,
+    pub craft_bukkit: Repository,
+    pub spigot: Repository,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::git::backend::CliBackend;
+    use crate::git::{init_repositories, init_repository, RepoResult, BUILD_DATA_URL};
+    use crate::RootContext;
+    use env_logger::WriteStyle;
+    use log::info;
+    use log::LevelFilter;
+    use std::path::Path;
+
+    fn init_logger() {
+        env_logger::builder()
+            .write_style(WriteStyle::Always)
+            .filter_level(LevelFilter::Info)
+            .try_init()
+            .ok();
+    }
+
+    #[tokio::test]
+    async fn init_build_data() -> RepoResult<()> {
+        init_logger();
+        let context = RootContext {
+            root_path: Path::new("build"),
+        };
+        let backend = CliBackend::new(context.root_path);
+        let repo = init_repository(&context, &backend, BUILD_DATA_URL, "BuildData").await?;
+        info!("{repo:?}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn init_all() -> RepoResult<()> {
+        init_logger();
+        let context = RootContext {
+            root_path: Path::new("build"),
+        };
+        let backend = CliBackend::new(context.root_path);
+        init_repositories(&context, &backend).await?;
+        Ok(())
+    }
+}
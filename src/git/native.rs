@@ -0,0 +1,107 @@
+use crate::git::backend::{Backend, BackendResult};
+use async_trait::async_trait;
+use git2::Repository as Git2Repository;
+use std::path::Path;
+
+/// Backend that drives git in-process using `libgit2` via the `git2`
+/// crate instead of shelling out to the `git` binary. Selected instead
+/// of [`CliBackend`](super::backend::CliBackend) when the
+/// `SBT_NATIVE_GIT` environment variable is set
+#[derive(Debug, Default)]
+pub struct NativeBackend;
+
+impl NativeBackend {
+    /// Creates a new native backend
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Backend for NativeBackend {
+    async fn clone(&self, working_dir: &Path, url: &str, name: &str) -> BackendResult<()> {
+        let dest = working_dir.join(name);
+        let url = url.to_string();
+        tokio::task::spawn_blocking(move || Git2Repository::clone(&url, dest)).await??;
+        Ok(())
+    }
+
+    async fn checkout(&self, path: &Path, reference: &str) -> BackendResult<()> {
+        let path = path.to_path_buf();
+        let reference = reference.to_string();
+        tokio::task::spawn_blocking(move || checkout_blocking(&path, &reference)).await??;
+        Ok(())
+    }
+
+    async fn current_commit(&self, path: &Path) -> BackendResult<String> {
+        let path = path.to_path_buf();
+        let commit = tokio::task::spawn_blocking(move || current_commit_blocking(&path)).await??;
+        Ok(commit)
+    }
+
+    async fn update_submodules(&self, path: &Path) -> BackendResult<()> {
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || update_submodules_blocking(&path)).await??;
+        Ok(())
+    }
+
+    fn is_valid(&self, path: &Path) -> bool {
+        Git2Repository::open(path).is_ok()
+    }
+}
+
+fn checkout_blocking(path: &Path, reference: &str) -> Result<(), git2::Error> {
+    let repo = Git2Repository::open(path)?;
+    let object = repo.revparse_single(reference)?;
+    repo.checkout_tree(&object, None)?;
+
+    match object.peel_to_commit() {
+        Ok(commit) => repo.set_head_detached(commit.id())?,
+        Err(_) => repo.set_head(reference)?,
+    }
+
+    Ok(())
+}
+
+fn current_commit_blocking(path: &Path) -> Result<String, git2::Error> {
+    let repo = Git2Repository::open(path)?;
+    let head = repo.head()?.peel_to_commit()?;
+    Ok(head.id().to_string())
+}
+
+fn update_submodules_blocking(path: &Path) -> Result<(), git2::Error> {
+    let repo = Git2Repository::open(path)?;
+    update_submodules_recursive(&repo)
+}
+
+fn update_submodules_recursive(repo: &Git2Repository) -> Result<(), git2::Error> {
+    for mut submodule in repo.submodules()? {
+        submodule.update(true, None)?;
+
+        if let Ok(sub_repo) = submodule.open() {
+            update_submodules_recursive(&sub_repo)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::git::backend::Backend;
+    use crate::git::native::NativeBackend;
+    use std::fs;
+
+    #[test]
+    fn is_valid_detects_repository() {
+        let backend = NativeBackend::new();
+        let dir = std::env::temp_dir().join("sbt-native-backend-is-valid-test");
+        fs::create_dir_all(&dir).unwrap();
+
+        assert!(!backend.is_valid(&dir));
+
+        git2::Repository::init(&dir).unwrap();
+        assert!(backend.is_valid(&dir));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
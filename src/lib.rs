@@ -1,13 +1,51 @@
+use crate::git::backend::{Backend, CliBackend};
+use crate::git::native::NativeBackend;
+use crate::git::{init_repositories, RepositoryError};
+use crate::version::{checkout_pinned_refs, read_build_info, VersionError};
+use derive_more::{Display, From};
 use std::path::Path;
 
 pub(crate) mod cmd;
 pub(crate) mod fs;
 pub(crate) mod git;
+pub(crate) mod version;
 
 pub(crate) struct RootContext<'a> {
     pub root_path: &'a Path,
 }
 
-pub fn run(root_path: &Path) {
+/// Set to opt into [`NativeBackend`] (in-process `libgit2`) instead of the
+/// default [`CliBackend`] (shelling out to the system `git` binary)
+const NATIVE_GIT_ENV: &str = "SBT_NATIVE_GIT";
+
+/// Picks the [`Backend`] implementation to drive git with, based on
+/// [`NATIVE_GIT_ENV`]
+fn select_backend(root_path: &Path) -> Box<dyn Backend> {
+    if std::env::var(NATIVE_GIT_ENV).is_ok() {
+        Box::new(NativeBackend::new())
+    } else {
+        Box::new(CliBackend::new(root_path))
+    }
+}
+
+#[derive(Debug, From, Display)]
+pub enum RunError {
+    #[display(fmt = "Failed to prepare repositories: {}", _0)]
+    Repository(RepositoryError),
+    #[display(fmt = "Failed to resolve build version: {}", _0)]
+    Version(VersionError),
+}
+
+/// Runs the build pipeline rooted at `root_path`, returning the exit
+/// code the embedding process should exit with so it can be forwarded
+/// on to `std::process::exit`
+pub async fn run(root_path: &Path) -> Result<i32, RunError> {
     let context = RootContext { root_path };
+    let backend = select_backend(root_path);
+
+    let repositories = init_repositories(&context, backend.as_ref()).await?;
+    let build_info = read_build_info(&repositories.build_data).await?;
+    checkout_pinned_refs(&repositories, &build_info, backend.as_ref()).await?;
+
+    Ok(0)
 }
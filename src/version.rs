@@ -0,0 +1,173 @@
+use crate::git::backend::Backend;
+use crate::git::{Repositories, Repository, RepositoryError};
+use derive_more::{Display, From};
+use log::info;
+use serde::Deserialize;
+use std::io;
+use tokio::fs::read_to_string;
+
+#[derive(Debug, From, Display)]
+pub enum VersionError {
+    #[display(fmt = "IO Error occurred while reading the version manifest: {}", _0)]
+    IO(io::Error),
+    #[display(fmt = "Failed to parse BuildData/info.json: {}", _0)]
+    Parse(serde_json::Error),
+    #[display(
+        fmt = "Failed to check out {} at ref `{}`: {}",
+        repo,
+        reference,
+        source
+    )]
+    Checkout {
+        repo: &'static str,
+        reference: String,
+        source: RepositoryError,
+    },
+}
+
+type VersionResult<T> = Result<T, VersionError>;
+
+/// The subset of `BuildData/info.json` needed to reproduce a specific
+/// Spigot release: which Minecraft version it targets and which commit
+/// of each repository to build from, plus the decompile/class-mapping
+/// configuration the later build steps need to apply to CraftBukkit
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildInfo {
+    pub minecraft_version: String,
+    pub spigot_version: String,
+    pub bukkit_ref: String,
+    pub craft_bukkit_ref: String,
+    pub spigot_ref: String,
+    pub access_transforms: Option<String>,
+    pub class_mappings: Option<String>,
+    pub member_mappings: Option<String>,
+    pub package_mappings: Option<String>,
+    pub decompile_command: Option<String>,
+}
+
+/// Reads and parses the `info.json` manifest out of an already cloned
+/// `BuildData` repository
+pub(crate) async fn read_build_info(build_data: &Repository) -> VersionResult<BuildInfo> {
+    let info_path = build_data.path().join("info.json");
+    let contents = read_to_string(&info_path).await?;
+    let info = serde_json::from_str(&contents)?;
+    Ok(info)
+}
+
+/// Checks out the ref named in `info` for each of Bukkit, CraftBukkit
+/// and Spigot, re-running submodule update afterwards so a ref that
+/// changed submodules ends up with the right ones checked out. Fails
+/// early with a descriptive error if any ref doesn't resolve
+pub(crate) async fn checkout_pinned_refs(
+    repos: &Repositories,
+    info: &BuildInfo,
+    backend: &dyn Backend,
+) -> VersionResult<()> {
+    checkout_ref(&repos.bukkit, &info.bukkit_ref, backend).await?;
+    checkout_ref(&repos.craft_bukkit, &info.craft_bukkit_ref, backend).await?;
+    checkout_ref(&repos.spigot, &info.spigot_ref, backend).await?;
+    Ok(())
+}
+
+async fn checkout_ref(
+    repo: &Repository,
+    reference: &str,
+    backend: &dyn Backend,
+) -> VersionResult<()> {
+    let as_checkout_error = |source: RepositoryError| VersionError::Checkout {
+        repo: repo.name(),
+        reference: reference.to_string(),
+        source,
+    };
+
+    backend
+        .checkout(repo.path(), reference)
+        .await
+        .map_err(RepositoryError::from)
+        .map_err(as_checkout_error)?;
+    repo.update_submodules(backend)
+        .await
+        .map_err(as_checkout_error)?;
+
+    // Read back the commit the ref actually resolved to so it ends up in
+    // the logs, and so a backend that silently no-ops a checkout doesn't
+    // go unnoticed
+    let commit = backend
+        .current_commit(repo.path())
+        .await
+        .map_err(RepositoryError::from)
+        .map_err(as_checkout_error)?;
+    info!("{} checked out at {commit}", repo.name());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::git::backend::CliBackend;
+    use crate::git::Repository;
+    use crate::version::{checkout_ref, BuildInfo, VersionError};
+    use git2::Repository as Git2Repository;
+    use std::path::Path;
+
+    /// Initializes a git repository at `path` with a single empty commit,
+    /// so checking out a ref that doesn't exist fails for the expected
+    /// reason rather than because the repository has no commits yet
+    fn init_repo_with_commit(path: &Path) {
+        let repo = Git2Repository::init(path).expect("git init should succeed");
+        let signature =
+            git2::Signature::now("test", "test@example.com").expect("signature should build");
+        let tree_id = repo
+            .index()
+            .expect("index should open")
+            .write_tree()
+            .expect("tree should write");
+        let tree = repo.find_tree(tree_id).expect("tree should be found");
+        repo.commit(Some("HEAD"), &signature, &signature, "initial", &tree, &[])
+            .expect("commit should succeed");
+    }
+
+    #[tokio::test]
+    async fn checkout_missing_ref_fails() {
+        let dir = std::env::temp_dir().join("sbt-version-checkout-missing-ref-test");
+        std::fs::create_dir_all(&dir).expect("temp dir should be created");
+        init_repo_with_commit(&dir);
+
+        let repo = Repository::new("unused", "TestRepo", dir.clone());
+        let backend = CliBackend::new(&dir);
+
+        let err = checkout_ref(&repo, "definitely-not-a-real-ref", &backend)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VersionError::Checkout { .. }));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn parse_info_json() {
+        let raw = r#"{
+            "minecraftVersion": "1.20.4",
+            "spigotVersion": "1.20.4-R0.1-SNAPSHOT",
+            "bukkitRef": "abc123",
+            "craftBukkitRef": "def456",
+            "spigotRef": "ghi789",
+            "accessTransforms": "bukkit-1.20.4.at",
+            "classMappings": "bukkit-1.20.4-cl.csrg",
+            "memberMappings": "bukkit-1.20.4-members.csrg",
+            "packageMappings": "package.srg",
+            "decompileCommand": "java -jar BuildData/bin/fernflower.jar {0} {1}"
+        }"#;
+
+        let info: BuildInfo = serde_json::from_str(raw).expect("info.json should parse");
+        assert_eq!(info.minecraft_version, "1.20.4");
+        assert_eq!(info.bukkit_ref, "abc123");
+        assert_eq!(info.craft_bukkit_ref, "def456");
+        assert_eq!(info.spigot_ref, "ghi789");
+        assert_eq!(
+            info.decompile_command.as_deref(),
+            Some("java -jar BuildData/bin/fernflower.jar {0} {1}")
+        );
+    }
+}